@@ -0,0 +1,50 @@
+use core::fmt;
+
+/// Errors that can occur while encoding, decoding, or exchanging a CEROS
+/// serial packet.
+///
+/// Every I/O and decode path in [`crate::protocol::CEROSSerial`] returns one
+/// of these instead of panicking, since a panic would abort the whole
+/// program in a `no_std` embedded kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerialError {
+    /// The underlying stream failed to read from or write to.
+    Io,
+    /// COBS decoding failed to produce a valid frame.
+    CobsDecode,
+    /// The packet did not start with a recognized header (the CEROS magic
+    /// number or a PROS `sout`/`serr`/`kdbg` prefix).
+    BadMagic,
+    /// The data-type byte did not match any known [`crate::protocol::DataType`] variant,
+    /// or, in PROS-compatibility mode, the data type has no PROS framing.
+    UnknownDataType,
+    /// The CRC-16/CCITT checksum did not match the checksum computed over
+    /// the decoded payload.
+    CrcMismatch,
+    /// The packet was shorter than its framing requires.
+    Incomplete,
+    /// A length-prefixed string was not valid UTF-8.
+    InvalidUtf8,
+    /// The packet's protocol-version byte doesn't match a version this
+    /// instance understands.
+    UnsupportedVersion(u8),
+}
+
+impl fmt::Display for SerialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SerialError::Io => write!(f, "I/O error communicating with the serial stream"),
+            SerialError::CobsDecode => write!(f, "failed to COBS-decode packet"),
+            SerialError::BadMagic => write!(f, "packet did not start with a recognized header"),
+            SerialError::UnknownDataType => write!(f, "packet had an unrecognized data type"),
+            SerialError::CrcMismatch => write!(f, "packet failed CRC-16/CCITT validation"),
+            SerialError::Incomplete => write!(f, "packet was too short to parse"),
+            SerialError::InvalidUtf8 => write!(f, "length-prefixed string was not valid UTF-8"),
+            SerialError::UnsupportedVersion(version) => {
+                write!(f, "unsupported CEROS protocol version {version}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for SerialError {}
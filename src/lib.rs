@@ -11,4 +11,6 @@ mod internal; // Internal functions that the user should not use
 #[cfg(feature = "v5")]
 pub mod serial; // Actual serial implementation
 
-pub mod protocol; // Contains the basic protocol implementation
\ No newline at end of file
+pub mod protocol; // Contains the basic protocol implementation
+pub mod error; // Contains the error type shared across the crate
+pub mod ser; // Contains the typed message (de)serialization layer
\ No newline at end of file
@@ -0,0 +1,208 @@
+use acid_io::{Read, Write};
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::SerialError;
+
+/// Serializes a value into the bytes that ride a CEROS packet. Paired with
+/// [`SerialRead`] so typed messages (kernel log structs, telemetry, ...)
+/// can go over [`crate::protocol::CEROSSerial::write_typed`] without the
+/// caller hand-packing a `Vec<u8>`.
+pub trait SerialWrite {
+    /// Writes `self` to `w` using this type's wire encoding.
+    fn write(&self, w: &mut impl Write) -> Result<(), SerialError>;
+}
+
+/// Deserializes a value out of the bytes carried by a CEROS packet. See
+/// [`crate::protocol::CEROSSerial::read_typed`].
+pub trait SerialRead: Sized {
+    /// Reads a `Self` from `r` using this type's wire encoding.
+    fn read(r: &mut impl Read) -> Result<Self, SerialError>;
+}
+
+macro_rules! impl_serial_int {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl SerialWrite for $ty {
+                fn write(&self, w: &mut impl Write) -> Result<(), SerialError> {
+                    w.write(&self.to_be_bytes()).map_err(|_| SerialError::Io)?;
+                    Ok(())
+                }
+            }
+
+            impl SerialRead for $ty {
+                fn read(r: &mut impl Read) -> Result<Self, SerialError> {
+                    let mut buf = [0u8; core::mem::size_of::<$ty>()];
+                    r.read_exact(&mut buf).map_err(|_| SerialError::Io)?;
+                    Ok(<$ty>::from_be_bytes(buf))
+                }
+            }
+        )*
+    };
+}
+
+// Big-endian, like the rest of the CEROS wire format.
+impl_serial_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+impl SerialWrite for str {
+    fn write(&self, w: &mut impl Write) -> Result<(), SerialError> {
+        (self.len() as u32).write(w)?;
+        w.write(self.as_bytes()).map_err(|_| SerialError::Io)?;
+        Ok(())
+    }
+}
+
+impl SerialWrite for String {
+    fn write(&self, w: &mut impl Write) -> Result<(), SerialError> {
+        self.as_str().write(w)
+    }
+}
+
+impl SerialRead for String {
+    fn read(r: &mut impl Read) -> Result<Self, SerialError> {
+        let len = u32::read(r)? as usize;
+        // `len` comes straight off the wire, so don't trust it enough to
+        // allocate it in one shot: read in bounded chunks instead. A
+        // corrupt or malicious length still fails fast via `read_exact`
+        // once the underlying stream runs out of bytes, rather than
+        // driving a multi-gigabyte allocation attempt first.
+        let mut buf = Vec::new();
+        let mut remaining = len;
+        let mut chunk = [0u8; 256];
+        while remaining > 0 {
+            let n = chunk.len().min(remaining);
+            r.read_exact(&mut chunk[..n]).map_err(|_| SerialError::Io)?;
+            buf.extend_from_slice(&chunk[..n]);
+            remaining -= n;
+        }
+        String::from_utf8(buf).map_err(|_| SerialError::InvalidUtf8)
+    }
+}
+
+impl<T: SerialWrite> SerialWrite for Vec<T> {
+    fn write(&self, w: &mut impl Write) -> Result<(), SerialError> {
+        (self.len() as u32).write(w)?;
+        for item in self {
+            item.write(w)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: SerialRead> SerialRead for Vec<T> {
+    fn read(r: &mut impl Read) -> Result<Self, SerialError> {
+        let len = u32::read(r)? as usize;
+        // Don't pre-reserve capacity for a `len` that came straight off
+        // the wire: a corrupt or malicious value would otherwise drive an
+        // allocation attempt for up to `u32::MAX` elements before a single
+        // element is read.
+        // `T::read` fails fast once the stream runs out of bytes, long
+        // before `len` iterations complete against a short payload.
+        let mut out = Vec::new();
+        for _ in 0..len {
+            out.push(T::read(r)?);
+        }
+        Ok(out)
+    }
+}
+
+/// Adapts a `Vec<u8>` into an [`acid_io::Write`] so [`SerialWrite`] impls
+/// can serialize into an in-memory buffer before it's framed and sent.
+pub(crate) struct VecWriter<'a>(pub &'a mut Vec<u8>);
+
+impl<'a> Write for VecWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> acid_io::Result<usize> {
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> acid_io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Adapts a byte slice into an [`acid_io::Read`] so [`SerialRead`] impls
+/// can deserialize out of an already-received packet's payload.
+pub(crate) struct SliceReader<'a> {
+    pub data: &'a [u8],
+    pub pos: usize,
+}
+
+impl<'a> Read for SliceReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> acid_io::Result<usize> {
+        let remaining = &self.data[self.pos..];
+        let n = remaining.len().min(buf.len());
+        buf[..n].copy_from_slice(&remaining[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T: SerialWrite + SerialRead + PartialEq + core::fmt::Debug>(value: T) {
+        let mut buf = Vec::new();
+        value.write(&mut VecWriter(&mut buf)).unwrap();
+        let mut reader = SliceReader { data: &buf, pos: 0 };
+        let decoded = T::read(&mut reader).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn integers_round_trip() {
+        round_trip(0x12u8);
+        round_trip(0x1234u16);
+        round_trip(0x1234_5678u32);
+        round_trip(0x1234_5678_9abc_def0u64);
+        round_trip(-1i8);
+        round_trip(-1234i16);
+        round_trip(-123_456_789i32);
+        round_trip(-123_456_789_012_345i64);
+    }
+
+    #[test]
+    fn string_round_trips() {
+        round_trip(String::from("hello, CEROS"));
+    }
+
+    #[test]
+    fn vec_round_trips() {
+        round_trip(Vec::from([1u32, 2, 3, 4]));
+    }
+
+    #[test]
+    fn string_read_fails_cleanly_on_truncated_length_prefix() {
+        // Claims a 100-byte string but only supplies 2 bytes of payload.
+        let mut buf = Vec::new();
+        100u32.write(&mut VecWriter(&mut buf)).unwrap();
+        buf.extend_from_slice(b"hi");
+
+        let mut reader = SliceReader { data: &buf, pos: 0 };
+        assert!(matches!(String::read(&mut reader), Err(SerialError::Io)));
+    }
+
+    #[test]
+    fn vec_read_fails_cleanly_on_truncated_length_prefix() {
+        // Claims 10 elements but supplies none.
+        let mut buf = Vec::new();
+        10u32.write(&mut VecWriter(&mut buf)).unwrap();
+
+        let mut reader = SliceReader { data: &buf, pos: 0 };
+        assert!(matches!(Vec::<u32>::read(&mut reader), Err(SerialError::Io)));
+    }
+
+    #[test]
+    fn string_read_rejects_invalid_utf8() {
+        let mut buf = Vec::new();
+        3u32.write(&mut VecWriter(&mut buf)).unwrap();
+        buf.extend_from_slice(&[0xff, 0xfe, 0xfd]);
+
+        let mut reader = SliceReader { data: &buf, pos: 0 };
+        assert!(matches!(
+            String::read(&mut reader),
+            Err(SerialError::InvalidUtf8)
+        ));
+    }
+}